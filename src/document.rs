@@ -0,0 +1,368 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::{Position, Row, SearchDirection};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Source {
+    Original,
+    Add,
+}
+
+#[derive(Clone, Copy)]
+struct Piece {
+    source: Source,
+    start: usize,
+    len: usize,
+}
+
+struct LineIndex {
+    text: String,
+    rows: Vec<Row>,
+    line_starts: Vec<usize>,
+    // Bytes consumed between the end of a row's (CR-stripped) content and the
+    // start of the next row: 1 for "\n", 2 for "\r\n", 0 for a last row that
+    // has no line break after it.
+    line_terms: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct Document {
+    original: String,
+    add: String,
+    pieces: Vec<Piece>,
+    file_name: Option<String>,
+    dirty: bool,
+    // Lazily rebuilt from `pieces` the first time it's needed after an edit,
+    // then reused by every row()/len()/find() call until the next insert or
+    // delete invalidates it. Without this, a single screen redraw (which
+    // calls row() once per terminal line) would reconstruct the whole
+    // document from its pieces on every single call.
+    line_index: RefCell<Option<LineIndex>>,
+}
+
+impl Document {
+    pub fn open(filename: &str) -> Result<Self, std::io::Error> {
+        let contents = fs::read_to_string(filename)?;
+        let len = contents.len();
+        let pieces = if len == 0 {
+            Vec::new()
+        } else {
+            vec![Piece {
+                source: Source::Original,
+                start: 0,
+                len,
+            }]
+        };
+        Ok(Self {
+            original: contents,
+            add: String::new(),
+            pieces,
+            file_name: Some(filename.to_string()),
+            dirty: false,
+            line_index: RefCell::new(None),
+        })
+    }
+
+    pub fn row(&self, index: usize) -> Option<Row> {
+        self.with_line_index(|line_index| line_index.rows.get(index).cloned())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.with_line_index(|index| index.rows.len())
+    }
+
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn save(&mut self) -> Result<(), std::io::Error> {
+        if let Some(file_name) = &self.file_name {
+            let mut file = fs::File::create(file_name)?;
+            let result = self.with_line_index(|index| -> Result<(), std::io::Error> {
+                for row in &index.rows {
+                    file.write_all(row.as_bytes())?;
+                    file.write_all(b"\n")?;
+                }
+                Ok(())
+            });
+            result?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, at: &Position, c: char) {
+        if c == '\n' {
+            self.insert_newline(at);
+            return;
+        }
+        let (line_count, offset, last_row_open) = self.with_line_index(|index| {
+            (
+                index.rows.len(),
+                Self::offset_in(index, at),
+                !index.text.is_empty() && !index.text.ends_with('\n'),
+            )
+        });
+        if at.y > line_count {
+            return;
+        }
+        self.dirty = true;
+        let mut text = String::new();
+        if at.y == line_count && last_row_open {
+            // Cursor is one past the last row (reachable via Down on the last
+            // line): force a new row instead of appending onto the last one.
+            text.push('\n');
+        }
+        let mut buf = [0; 4];
+        text.push_str(c.encode_utf8(&mut buf));
+        self.insert_at_offset(offset, &text);
+    }
+
+    pub fn insert_newline(&mut self, at: &Position) {
+        let (line_count, offset) =
+            self.with_line_index(|index| (index.rows.len(), Self::offset_in(index, at)));
+        if at.y > line_count {
+            return;
+        }
+        self.dirty = true;
+        self.insert_at_offset(offset, "\n");
+    }
+
+    pub fn delete(&mut self, at: &Position) {
+        let plan = self.with_line_index(|index| {
+            if at.y >= index.rows.len() {
+                return None;
+            }
+            let row_len = index.rows[at.y].len();
+            if at.x == row_len {
+                return Some(if at.y + 1 < index.rows.len() {
+                    (Self::offset_in(index, at), index.line_terms[at.y])
+                } else {
+                    (0, 0)
+                });
+            }
+            let grapheme_len = index.rows[at.y]
+                .as_str()
+                .graphemes(true)
+                .nth(at.x)
+                .map(str::len)
+                .unwrap_or(0);
+            Some((Self::offset_in(index, at), grapheme_len))
+        });
+
+        if let Some((offset, len)) = plan {
+            self.dirty = true;
+            if len > 0 {
+                self.delete_range(offset, len);
+            }
+        }
+    }
+
+    pub fn find(&self, query: &str, at: &Position, direction: SearchDirection) -> Option<Position> {
+        self.with_line_index(|index| {
+            let rows = &index.rows;
+            if at.y >= rows.len() {
+                return None;
+            }
+            let mut position = Position { x: at.x, y: at.y };
+
+            let start = if direction == SearchDirection::Forward {
+                at.y
+            } else {
+                0
+            };
+            let end = if direction == SearchDirection::Forward {
+                rows.len()
+            } else {
+                at.y.saturating_add(1)
+            };
+
+            for _ in start..end {
+                if let Some(row) = rows.get(position.y) {
+                    if let Some(x) = row.find(query, position.x, direction) {
+                        position.x = x;
+                        return Some(position);
+                    }
+                    if direction == SearchDirection::Forward {
+                        position.y = position.y.saturating_add(1);
+                        position.x = 0;
+                    } else {
+                        position.y = position.y.saturating_sub(1);
+                        position.x = rows[position.y].len();
+                    }
+                } else {
+                    return None;
+                }
+            }
+            None
+        })
+    }
+
+    fn piece_slice(&self, piece: &Piece) -> &str {
+        let buffer = match piece.source {
+            Source::Original => &self.original,
+            Source::Add => &self.add,
+        };
+        &buffer[piece.start..piece.start + piece.len]
+    }
+
+    fn text(&self) -> String {
+        let mut result = String::new();
+        for piece in &self.pieces {
+            result.push_str(self.piece_slice(piece));
+        }
+        result
+    }
+
+    fn rebuild_line_index(&self) -> LineIndex {
+        let text = self.text();
+        if text.is_empty() {
+            return LineIndex {
+                text,
+                rows: Vec::new(),
+                line_starts: Vec::new(),
+                line_terms: Vec::new(),
+            };
+        }
+
+        // Split on '\n' (like str::lines()) and then strip a trailing '\r'
+        // from each segment, so CRLF files don't carry the '\r' into row
+        // content, grapheme counts, or search.
+        let segments: Vec<&str> = text.split('\n').collect();
+        let last = segments.len() - 1;
+        let mut rows = Vec::with_capacity(segments.len());
+        let mut line_starts = Vec::with_capacity(segments.len());
+        let mut line_terms = Vec::with_capacity(segments.len());
+        let mut offset = 0;
+        for (i, segment) in segments.iter().enumerate() {
+            let is_trailing_empty = i == last && segment.is_empty() && segments.len() > 1;
+            if !is_trailing_empty {
+                let content = segment.strip_suffix('\r').unwrap_or(segment);
+                let term = if i == last {
+                    0
+                } else {
+                    1 + usize::from(content.len() != segment.len())
+                };
+                line_starts.push(offset);
+                line_terms.push(term);
+                rows.push(Row::from(content));
+            }
+            offset += segment.len() + 1;
+        }
+
+        LineIndex {
+            text,
+            rows,
+            line_starts,
+            line_terms,
+        }
+    }
+
+    fn with_line_index<T>(&self, f: impl FnOnce(&LineIndex) -> T) -> T {
+        if self.line_index.borrow().is_none() {
+            let rebuilt = self.rebuild_line_index();
+            *self.line_index.borrow_mut() = Some(rebuilt);
+        }
+        let guard = self.line_index.borrow();
+        f(guard.as_ref().unwrap())
+    }
+
+    fn offset_in(index: &LineIndex, at: &Position) -> usize {
+        if at.y >= index.rows.len() {
+            return index.text.len();
+        }
+        let row = &index.rows[at.y];
+        let col_bytes: usize = row.as_str().graphemes(true).take(at.x).map(str::len).sum();
+        index.line_starts[at.y] + col_bytes.min(row.as_str().len())
+    }
+
+    fn insert_at_offset(&mut self, offset: usize, text: &str) {
+        let add_start = self.add.len();
+        self.add.push_str(text);
+        let new_piece = Piece {
+            source: Source::Add,
+            start: add_start,
+            len: text.len(),
+        };
+
+        let mut pos = 0;
+        let mut target = None;
+        for (index, piece) in self.pieces.iter().enumerate() {
+            if offset < pos + piece.len {
+                target = Some((index, offset - pos, *piece));
+                break;
+            }
+            pos += piece.len;
+        }
+
+        match target {
+            None => self.pieces.push(new_piece),
+            Some((index, 0, _)) => {
+                self.pieces.insert(index, new_piece);
+            }
+            Some((index, split_at, piece)) => {
+                let left = Piece {
+                    source: piece.source,
+                    start: piece.start,
+                    len: split_at,
+                };
+                let right = Piece {
+                    source: piece.source,
+                    start: piece.start + split_at,
+                    len: piece.len - split_at,
+                };
+                self.pieces.splice(index..=index, [left, new_piece, right]);
+            }
+        }
+
+        *self.line_index.borrow_mut() = None;
+    }
+
+    fn delete_range(&mut self, start: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let end = start + len;
+        let mut pos = 0;
+        let mut new_pieces = Vec::with_capacity(self.pieces.len());
+
+        for piece in &self.pieces {
+            let piece_start = pos;
+            let piece_end = pos + piece.len;
+            pos = piece_end;
+
+            if piece_end <= start || piece_start >= end {
+                new_pieces.push(*piece);
+                continue;
+            }
+            if piece_start < start {
+                new_pieces.push(Piece {
+                    source: piece.source,
+                    start: piece.start,
+                    len: start - piece_start,
+                });
+            }
+            if piece_end > end {
+                let trim = end - piece_start;
+                new_pieces.push(Piece {
+                    source: piece.source,
+                    start: piece.start + trim,
+                    len: piece_end - end,
+                });
+            }
+        }
+        self.pieces = new_pieces;
+
+        *self.line_index.borrow_mut() = None;
+    }
+}
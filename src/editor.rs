@@ -1,8 +1,13 @@
 use std::env;
+use std::time::{Duration, Instant};
 use crate::{document, row, Document, Row, Terminal};
+use termion::color;
 use termion::event::Key;
+use unicode_segmentation::UnicodeSegmentation;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+const STATUS_FG_COLOR: color::Rgb = color::Rgb(63, 63, 63);
+const STATUS_BG_COLOR: color::Rgb = color::Rgb(239, 239, 239);
 
 const MOVE_KEYS: [Key; 8] = 
 [Key::Left, 
@@ -14,30 +19,56 @@ const MOVE_KEYS: [Key; 8] =
  Key::Char('k'),
  Key::Char('l')];
 
-const SHORTCUT_MOVE_KEYS: [Key; 4] = 
+const SHORTCUT_MOVE_KEYS: [Key; 4] =
 [Key::PageDown,
  Key::PageUp,
  Key::Home,
  Key::End];
 
-#[derive(Default)]
+const QUIT_TIMES: u8 = 3;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Default, Copy, Clone)]
 pub struct Position {
     pub x: usize,
     pub y: usize,
 }
 
+struct StatusMessage {
+    text: String,
+    time: Instant,
+}
+
+impl StatusMessage {
+    fn from(message: String) -> Self {
+        Self {
+            text: message,
+            time: Instant::now(),
+        }
+    }
+}
+
 pub struct Editor {
     want_quit: bool,
     terminal: Terminal,
     cursor_position: Position,
     offset: Position,
     document: Document,
+    quit_times: u8,
+    status_message: StatusMessage,
+    highlighted_match: Option<(Position, usize)>,
 }
 
 
 impl Editor {
     pub fn default() -> Self {
         let args: Vec<String> = env::args().collect();
+        let initial_status = String::from("HELP: Ctrl-S = save | Ctrl-F = find | Ctrl-P = quit");
         let document = if args.len() > 1 {
             let file_name = &args[1];
             Document::open(&file_name).unwrap_or_default()
@@ -51,6 +82,9 @@ impl Editor {
             cursor_position: Position::default(),
             offset: Position::default(),
             document,
+            quit_times: QUIT_TIMES,
+            status_message: StatusMessage::from(initial_status),
+            highlighted_match: None,
         }
     }
 
@@ -80,20 +114,44 @@ impl Editor {
         println!("{}\r", msg);
     }
 
-    pub fn draw_row(&self, row: &Row) {
+    pub fn draw_row(&self, row: &Row, row_index: usize) {
         let start = self.offset.x;
         let width = self.terminal.size().width as usize;
         let end = self.offset.x + width;
-        let row = row.render(start, end);
-        println!("{}\r", row);
+        let rendered = row.render(start, end);
+
+        if let Some((position, len)) = self.highlighted_match {
+            if position.y == row_index {
+                let rx = row.cx_to_rx(position.x);
+                let graphemes: Vec<&str> = rendered.graphemes(true).collect();
+                let hl_start = rx.saturating_sub(start).min(graphemes.len());
+                let hl_end = hl_start.saturating_add(len).min(graphemes.len());
+                if hl_start < hl_end {
+                    let before: String = graphemes[..hl_start].concat();
+                    let middle: String = graphemes[hl_start..hl_end].concat();
+                    let after: String = graphemes[hl_end..].concat();
+                    println!(
+                        "{}{}{}{}{}\r",
+                        before,
+                        termion::style::Invert,
+                        middle,
+                        termion::style::Reset,
+                        after
+                    );
+                    return;
+                }
+            }
+        }
+        println!("{}\r", rendered);
     }
-    
+
     fn draw_rows(&self) {
         let height = self.terminal.size().height;
-        for terminal_row in 0..height - 1 {
+        for terminal_row in 0..height {
             Terminal::clear_current_line();
-            if let Some(row) = self.document.row(terminal_row as usize + self.offset.y) {
-                self.draw_row(row);
+            let document_row = terminal_row as usize + self.offset.y;
+            if let Some(row) = self.document.row(document_row) {
+                self.draw_row(&row, document_row);
             } else if self.document.is_empty() && terminal_row == height / 3 {
                 self.welcome_msg();
             } else {
@@ -102,16 +160,98 @@ impl Editor {
         }
     }
 
+    fn draw_status_bar(&self) {
+        let width = self.terminal.size().width as usize;
+        let modified_indicator = if self.document.is_dirty() {
+            " (modified)"
+        } else {
+            ""
+        };
+
+        let mut file_name = self.document.file_name().unwrap_or("[No Name]").to_string();
+        file_name.truncate(20);
+
+        let mut status = format!(
+            "{} - {} lines{}",
+            file_name,
+            self.document.len(),
+            modified_indicator
+        );
+
+        let line_indicator = format!(
+            "{}/{}",
+            self.cursor_position.y.saturating_add(1),
+            self.document.len()
+        );
+
+        let len = status.len() + line_indicator.len();
+        if width > len {
+            status.push_str(&" ".repeat(width - len));
+        }
+        status = format!("{}{}", status, line_indicator);
+        status.truncate(width);
+
+        Terminal::set_bg_color(STATUS_BG_COLOR);
+        Terminal::set_fg_color(STATUS_FG_COLOR);
+        println!("{}\r", status);
+        Terminal::reset_fg_color();
+        Terminal::reset_bg_color();
+    }
+
+    fn draw_message_bar(&self) {
+        Terminal::clear_current_line();
+        let message = &self.status_message;
+        if Instant::now() - message.time < Duration::new(5, 0) {
+            let mut text = message.text.clone();
+            text.truncate(self.terminal.size().width as usize);
+            print!("{}", text);
+        }
+    }
+
     fn process_keypress(&mut self) -> Result<(), std::io::Error> {
         let pressed_key = Terminal::read_key()?;
-        // Exit Cmd
-        if pressed_key == Key::Ctrl('p') {
-            self.want_quit = true;
-        } else if Self::is_move_key(pressed_key) {
-            self.move_cursor(pressed_key);
-        } else if Self::is_move_shortcut(pressed_key) {
-            self.move_cursor(pressed_key);
+        match pressed_key {
+            Key::Ctrl('p') => {
+                if self.document.is_dirty() && self.quit_times > 0 {
+                    self.status_message = StatusMessage::from(format!(
+                        "WARNING! File has unsaved changes. Press Ctrl-P {} more time(s) to quit.",
+                        self.quit_times
+                    ));
+                    self.quit_times -= 1;
+                    return Ok(());
+                }
+                self.want_quit = true;
+            }
+            Key::Ctrl('s') => {
+                if self.document.save().is_err() {
+                    self.status_message = StatusMessage::from("Error writing file!".to_string());
+                } else {
+                    self.status_message = StatusMessage::from("File saved successfully.".to_string());
+                }
+            }
+            Key::Ctrl('f') => self.search()?,
+            Key::Char(c) if !c.is_control() => {
+                self.document.insert(&self.cursor_position, c);
+                self.move_cursor(Key::Right);
+            }
+            Key::Char('\n') => {
+                self.document.insert(&self.cursor_position, '\n');
+                self.cursor_position.y = self.cursor_position.y.saturating_add(1);
+                self.cursor_position.x = 0;
+            }
+            Key::Delete => self.document.delete(&self.cursor_position),
+            Key::Backspace => {
+                if self.cursor_position.x > 0 || self.cursor_position.y > 0 {
+                    self.move_cursor(Key::Left);
+                    self.document.delete(&self.cursor_position);
+                }
+            }
+            _ if Self::is_move_key(pressed_key) || Self::is_move_shortcut(pressed_key) => {
+                self.move_cursor(pressed_key);
+            }
+            _ => (),
         }
+        self.quit_times = QUIT_TIMES;
         self.scroll();
         Ok(())
     }
@@ -124,10 +264,80 @@ impl Editor {
         return MOVE_KEYS.contains(&key);
     }
 
+    fn search(&mut self) -> Result<(), std::io::Error> {
+        let old_position = self.cursor_position;
+        let old_offset = self.offset;
+        let mut direction = SearchDirection::Forward;
+
+        let query = self.prompt(
+            "Search (ESC to cancel, Arrows to navigate): ",
+            |editor, key, query| {
+                let mut moved = false;
+                match key {
+                    Key::Right | Key::Down => {
+                        direction = SearchDirection::Forward;
+                        editor.move_cursor(Key::Right);
+                        moved = true;
+                    }
+                    Key::Left | Key::Up => direction = SearchDirection::Backward,
+                    _ => direction = SearchDirection::Forward,
+                }
+
+                if let Some(position) = editor.document.find(query, &editor.cursor_position, direction) {
+                    editor.cursor_position = position;
+                    editor.scroll();
+                    editor.highlighted_match = Some((position, query.graphemes(true).count()));
+                } else if moved {
+                    editor.move_cursor(Key::Left);
+                }
+            },
+        )?;
+
+        if query.is_none() {
+            self.cursor_position = old_position;
+            self.offset = old_offset;
+        }
+        self.highlighted_match = None;
+        self.scroll();
+        Ok(())
+    }
+
+    fn prompt<C>(&mut self, prompt: &str, mut callback: C) -> Result<Option<String>, std::io::Error>
+    where
+        C: FnMut(&mut Self, Key, &str),
+    {
+        let mut result = String::new();
+        loop {
+            self.status_message = StatusMessage::from(format!("{}{}", prompt, result));
+            self.refresh_screen()?;
+            let key = Terminal::read_key()?;
+            match key {
+                Key::Backspace => {
+                    let new_len = result.len().saturating_sub(1);
+                    result.truncate(new_len);
+                }
+                Key::Char('\n') => break,
+                Key::Char(c) if !c.is_control() => result.push(c),
+                Key::Esc => {
+                    result.truncate(0);
+                    break;
+                }
+                _ => (),
+            }
+            callback(self, key, &result);
+        }
+        self.status_message = StatusMessage::from(String::new());
+        if result.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(result))
+    }
+
     fn scroll(&mut self) {
         let Position {x, y} = self.cursor_position;
         let width = self.terminal.size().width as usize;
         let height = self.terminal.size().height as usize;
+        let x = self.document.row(y).map_or(0, |row| row.cx_to_rx(x));
         let mut offset = &mut self.offset;
 
         if y < offset.y {
@@ -194,8 +404,14 @@ impl Editor {
             print!("Bye Now!");
         } else {
             self.draw_rows();
+            self.draw_status_bar();
+            self.draw_message_bar();
+            let render_x = self
+                .document
+                .row(self.cursor_position.y)
+                .map_or(0, |row| row.cx_to_rx(self.cursor_position.x));
             Terminal::cursor_position(&Position {
-                x: self.cursor_position.x.saturating_sub(self.offset.x),
+                x: render_x.saturating_sub(self.offset.x),
                 y: self.cursor_position.y.saturating_sub(self.offset.y),
             });
         }
@@ -5,6 +5,7 @@ mod terminal;
 use editor::Editor;
 pub use terminal::Terminal;
 pub use editor::Position;
+pub use editor::SearchDirection;
 pub use document::Document;
 pub use row::Row;
 
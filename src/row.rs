@@ -0,0 +1,148 @@
+use std::cmp;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::SearchDirection;
+
+const TAB_STOP: usize = 4;
+
+#[derive(Default, Clone)]
+pub struct Row {
+    string: String,
+    len: usize,
+}
+
+impl From<&str> for Row {
+    fn from(slice: &str) -> Self {
+        let mut row = Self {
+            string: String::from(slice),
+            len: 0,
+        };
+        row.update_len();
+        row
+    }
+}
+
+impl Row {
+    pub fn render(&self, start: usize, end: usize) -> String {
+        let end = cmp::min(end, self.len);
+        let start = cmp::min(start, end);
+        let mut result = String::new();
+        let mut rx = 0;
+        for grapheme in self.string[..].graphemes(true).skip(start).take(end.saturating_sub(start)) {
+            if grapheme == "\t" {
+                let spaces = TAB_STOP - rx % TAB_STOP;
+                result.push_str(&" ".repeat(spaces));
+                rx += spaces;
+            } else {
+                result.push_str(grapheme);
+                rx += 1;
+            }
+        }
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.string.as_bytes()
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    pub fn insert(&mut self, at: usize, c: char) {
+        if at >= self.len() {
+            self.string.push(c);
+        } else {
+            let mut result: String = self.string[..].graphemes(true).take(at).collect();
+            let remainder: String = self.string[..].graphemes(true).skip(at).collect();
+            result.push(c);
+            result.push_str(&remainder);
+            self.string = result;
+        }
+        self.update_len();
+    }
+
+    pub fn delete(&mut self, at: usize) {
+        if at >= self.len() {
+            return;
+        }
+        let mut result: String = self.string[..].graphemes(true).take(at).collect();
+        let remainder: String = self.string[..].graphemes(true).skip(at + 1).collect();
+        result.push_str(&remainder);
+        self.string = result;
+        self.update_len();
+    }
+
+    pub fn append(&mut self, new: &Self) {
+        self.string = format!("{}{}", self.string, new.string);
+        self.update_len();
+    }
+
+    pub fn split(&mut self, at: usize) -> Self {
+        let beginning: String = self.string[..].graphemes(true).take(at).collect();
+        let remainder: String = self.string[..].graphemes(true).skip(at).collect();
+        self.string = beginning;
+        self.update_len();
+        Self::from(&remainder[..])
+    }
+
+    pub fn cx_to_rx(&self, cx: usize) -> usize {
+        let mut rx = 0;
+        for grapheme in self.string[..].graphemes(true).take(cx) {
+            if grapheme == "\t" {
+                rx += TAB_STOP - rx % TAB_STOP;
+            } else {
+                rx += 1;
+            }
+        }
+        rx
+    }
+
+    fn update_len(&mut self) {
+        self.len = self.string[..].graphemes(true).count();
+    }
+
+    pub fn find(&self, query: &str, after: usize, direction: SearchDirection) -> Option<usize> {
+        if after > self.len || query.is_empty() {
+            return None;
+        }
+        let start = if direction == SearchDirection::Forward {
+            after
+        } else {
+            0
+        };
+        let end = if direction == SearchDirection::Forward {
+            self.len
+        } else {
+            after
+        };
+
+        let substring: String = self.string[..]
+            .graphemes(true)
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .collect();
+
+        let matching_byte_index = if direction == SearchDirection::Forward {
+            substring.find(query)
+        } else {
+            substring.rfind(query)
+        };
+
+        if let Some(matching_byte_index) = matching_byte_index {
+            for (grapheme_index, (byte_index, _)) in substring[..].grapheme_indices(true).enumerate() {
+                if matching_byte_index == byte_index {
+                    return Some(start + grapheme_index);
+                }
+            }
+        }
+        None
+    }
+}